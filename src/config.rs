@@ -1,17 +1,75 @@
-use nvim_oxi::{api::{self, Window}, Function};
+use nvim_oxi::{
+    api::{self, Buffer, Window},
+    Function,
+};
 use serde::Deserialize;
 
 use crate::error::Error as DiffDirsError;
 
+const DEFAULT_MAX_OPEN_DIFF_TABS: usize = 10;
+const DEFAULT_DASHBOARD_JUMP_KEYMAP: &str = "<cr>";
+
+fn default_max_open_diff_tabs() -> usize {
+    DEFAULT_MAX_OPEN_DIFF_TABS
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     left_diff_opt_fn: Option<Function<Window, ()>>,
     right_diff_opt_fn: Option<Function<Window, ()>>,
+    #[serde(default)]
+    only_changed: bool,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    respect_gitignore: bool,
+    #[serde(default = "default_max_open_diff_tabs")]
+    max_open_diff_tabs: usize,
+    dashboard_opt_fn: Option<Function<Buffer, ()>>,
+    #[serde(default)]
+    dashboard_jump_keymap: Option<String>,
+    #[serde(default)]
+    seed_merged_output: bool,
 }
 
 impl Config {
     pub const fn new() -> Self {
-        Self { left_diff_opt_fn: None, right_diff_opt_fn: None }
+        Self {
+            left_diff_opt_fn: None,
+            right_diff_opt_fn: None,
+            only_changed: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            respect_gitignore: false,
+            max_open_diff_tabs: DEFAULT_MAX_OPEN_DIFF_TABS,
+            dashboard_opt_fn: None,
+            dashboard_jump_keymap: None,
+            seed_merged_output: false,
+        }
+    }
+
+    pub fn only_changed(&self) -> bool {
+        self.only_changed
+    }
+
+    /// Glob patterns a relative file path must match at least one of; an empty list matches all.
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+
+    pub fn respect_gitignore(&self) -> bool {
+        self.respect_gitignore
+    }
+
+    /// Diff tabs kept open before the least-recently-visited is closed; `0` disables the cap.
+    pub fn max_open_diff_tabs(&self) -> usize {
+        self.max_open_diff_tabs
     }
 
     pub fn set_left_diff_opt(&self, win: Window) -> Result<(), DiffDirsError> {
@@ -29,4 +87,24 @@ impl Config {
         }
         Ok(())
     }
+
+    /// Keymap (in the dashboard buffer) that jumps to the diff tab for the entry under the cursor.
+    pub fn dashboard_jump_keymap(&self) -> &str {
+        self.dashboard_jump_keymap
+            .as_deref()
+            .unwrap_or(DEFAULT_DASHBOARD_JUMP_KEYMAP)
+    }
+
+    pub fn set_dashboard_opt(&self, buf: Buffer) -> Result<(), DiffDirsError> {
+        api::command("setlocal buftype=nofile bufhidden=wipe nobuflisted noswapfile nomodifiable")?;
+        if let Some(f) = &self.dashboard_opt_fn {
+            f.call(buf)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a not-yet-existing three-pane output file should be seeded with a merged diff.
+    pub fn seed_merged_output(&self) -> bool {
+        self.seed_merged_output
+    }
 }
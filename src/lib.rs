@@ -1,8 +1,10 @@
 use error::Error as DiffDirsError;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
 use serde::Deserialize;
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     path::{Path, PathBuf},
 };
 use walkdir::WalkDir;
@@ -11,8 +13,11 @@ use nvim_oxi::{
     self,
     api::{
         self,
-        opts::{CmdOpts, CreateCommandOpts, SetKeymapOpts},
-        types::{CmdInfos, CommandArgs, CommandModifiers, CommandNArgs, Mode, SplitModifier},
+        opts::{CmdOpts, CreateAutocmdOpts, CreateCommandOpts, SetKeymapOpts},
+        types::{
+            AutocmdCallbackArgs, CmdInfos, CommandArgs, CommandModifiers, CommandNArgs, Mode,
+            SplitModifier,
+        },
         Buffer, StringOrFunction, TabPage,
     },
     print, Array, Dictionary, Function, Object,
@@ -20,6 +25,8 @@ use nvim_oxi::{
 
 mod config;
 mod error;
+mod merge;
+mod status;
 
 #[derive(Debug)]
 enum DiffDirType {
@@ -33,8 +40,18 @@ impl Default for DiffDirType {
     }
 }
 
+/// A diffed path's realized tab (if any has been opened yet) and its change status.
+#[derive(Debug, Default)]
+struct DiffEntry {
+    tab: Option<TabPage>,
+    status: Option<status::FileStatus>,
+}
+
 thread_local! {
-    static DIFF_FILES: RefCell<BTreeMap<PathBuf, TabPage>> = const {RefCell::new(BTreeMap::new())};
+    static DIFF_FILES: RefCell<BTreeMap<PathBuf, DiffEntry>> =
+        const {RefCell::new(BTreeMap::new())};
+    // Paths of currently-open diff tabs, least- to most-recently-visited.
+    static DIFF_TAB_LRU: RefCell<VecDeque<PathBuf>> = const {RefCell::new(VecDeque::new())};
     static DIFF_DIRS: RefCell<DiffDirType> = RefCell::new(Default::default());
     static CONFIG: RefCell<config::Config> = const {RefCell::new(config::Config::new())};
 }
@@ -44,10 +61,13 @@ fn diffdirs() -> nvim_oxi::Result<Dictionary> {
     let setup_fn: Function<Object, Result<(), DiffDirsError>> = Function::from_fn(setup);
     let jumb_tab_fn: Function<String, Result<(), DiffDirsError>> =
         Function::from_fn(jump_to_diff_tab);
+    let open_dashboard_fn: Function<(), Result<(), DiffDirsError>> =
+        Function::from_fn(open_dashboard);
     Ok(Dictionary::from_iter([
         ("setup", setup_fn.to_object()),
         ("diff_files", Function::from_fn(diff_files).to_object()),
         ("jump_diff_tab", jumb_tab_fn.to_object()),
+        ("open_dashboard", open_dashboard_fn.to_object()),
     ]))
 }
 
@@ -78,43 +98,143 @@ fn diff_files(_: ()) -> Vec<String> {
     })
 }
 
+fn close_tab(tab: &TabPage) -> Result<(), DiffDirsError> {
+    if !tab.is_valid() {
+        return Ok(());
+    }
+    let current = api::get_current_tabpage();
+    api::set_current_tabpage(tab)?;
+    api::command("tabclose")?;
+    if current.is_valid() {
+        api::set_current_tabpage(&current)?;
+    }
+    Ok(())
+}
+
+fn touch_diff_tab_lru(path: &Path) {
+    DIFF_TAB_LRU.with_borrow_mut(|lru| {
+        lru.retain(|p| p != path);
+        lru.push_back(path.to_owned());
+    });
+}
+
+/// Pops the least-recently-visited path once more than `cap` tabs are open (`cap == 0`: no limit).
+fn evict_stale_diff_tab(cap: usize) -> Option<PathBuf> {
+    DIFF_TAB_LRU.with_borrow_mut(|lru| {
+        if cap > 0 && lru.len() > cap {
+            lru.pop_front()
+        } else {
+            None
+        }
+    })
+}
+
+/// Jumps to the diff tab for `path`, lazily opening it if it hasn't been materialized yet or
+/// was closed behind our back, then evicts the least-recently-visited tab past the LRU cap.
 fn jump_to_diff_tab(path: String) -> Result<(), DiffDirsError> {
-    DIFF_FILES.with_borrow_mut(|files| {
-        files
-            .get_mut(<str as AsRef<Path>>::as_ref(&path))
-            .ok_or_else(|| DiffDirsError::other(format!("invalid diff path: {path}")))
-            .and_then(|tab| {
-                if tab.is_valid() {
-                    Ok(api::set_current_tabpage(tab)?)
-                } else {
-                    let path = Path::new(&path);
-                    DIFF_DIRS.with_borrow(|dirs| {
-                        CONFIG.with_borrow(|config| {
-                            match dirs {
-                                DiffDirType::Two(left_dir, right_dir) => {
-                                    TwoPaneDiff {
-                                        left_dir,
-                                        right_dir,
-                                    }
-                                    .open_diff_tab(path, "tabedit", config)?;
-                                    *tab = api::get_current_tabpage();
-                                }
-                                DiffDirType::Three(left_dir, right_dir, output_dir) => {
-                                    ThreePaneDiff {
-                                        left_dir,
-                                        right_dir,
-                                        output_dir,
-                                    }
-                                    .open_diff_tab(path, "tabedit", config)?;
-                                    *tab = api::get_current_tabpage();
-                                }
-                            };
-                            Ok(())
-                        })
-                    })
+    let file = PathBuf::from(&path);
+
+    let opened = DIFF_FILES.with_borrow_mut(|files| -> Result<bool, DiffDirsError> {
+        let entry = files
+            .get_mut(&file)
+            .ok_or_else(|| DiffDirsError::other(format!("invalid diff path: {path}")))?;
+
+        if let Some(tab) = entry.tab.as_ref() {
+            if tab.is_valid() {
+                api::set_current_tabpage(tab)?;
+                return Ok(false);
+            }
+        }
+
+        DIFF_DIRS.with_borrow(|dirs| {
+            CONFIG.with_borrow(|config| match dirs {
+                DiffDirType::Two(left_dir, right_dir) => TwoPaneDiff {
+                    left_dir,
+                    right_dir,
+                }
+                .open_diff_tab(&file, "tabedit", config),
+                DiffDirType::Three(left_dir, right_dir, output_dir) => ThreePaneDiff {
+                    left_dir,
+                    right_dir,
+                    output_dir,
                 }
+                .open_diff_tab(&file, "tabedit", config),
             })
-    })
+        })?;
+        entry.tab = Some(api::get_current_tabpage());
+        Ok(true)
+    })?;
+
+    touch_diff_tab_lru(&file);
+    if opened {
+        let cap = CONFIG.with_borrow(config::Config::max_open_diff_tabs);
+        if let Some(evicted) = evict_stale_diff_tab(cap) {
+            DIFF_FILES.with_borrow_mut(|files| -> Result<(), DiffDirsError> {
+                if let Some(tab) = files.get_mut(&evicted).and_then(|entry| entry.tab.take()) {
+                    close_tab(&tab)?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders every diffed path into the dashboard buffer, one per line, prefixed by its status.
+fn render_dashboard(buf: &mut Buffer) -> Result<(), DiffDirsError> {
+    let lines: Vec<String> = DIFF_FILES.with_borrow(|files| {
+        files
+            .iter()
+            .map(|(path, entry)| match &entry.status {
+                Some(status) => format!("{} {}", status.label(), path.to_string_lossy()),
+                None => format!("      {}", path.to_string_lossy()),
+            })
+            .collect()
+    });
+    buf.set_option("modifiable", true)?;
+    buf.set_lines(.., false, lines)?;
+    buf.set_option("modifiable", false)?;
+    Ok(())
+}
+
+fn dashboard_path_from_line(line: &str) -> Result<String, DiffDirsError> {
+    let path = line.split_once(' ').map_or(line, |(_, rest)| rest).trim();
+    if path.is_empty() {
+        return Err(DiffDirsError::other("no diffed path on the current line"));
+    }
+    Ok(path.to_owned())
+}
+
+/// Opens a scratch buffer listing every diffed path; the configured jump keymap (`<cr>` by
+/// default) on a line jumps to its diff tab.
+fn open_dashboard(_: ()) -> Result<(), DiffDirsError> {
+    api::cmd(
+        &CmdInfos::builder().cmd("new").build(),
+        &CmdOpts::builder().output(false).build(),
+    )?;
+    let mut buf = api::get_current_buf();
+
+    CONFIG.with_borrow(|config| -> Result<(), DiffDirsError> {
+        config.set_dashboard_opt(buf.clone())?;
+
+        Ok(buf.set_keymap(
+            Mode::Normal,
+            config.dashboard_jump_keymap(),
+            "",
+            &SetKeymapOpts::builder()
+                .desc("Jump to the diff for the entry under the cursor")
+                .callback(|_: ()| -> Result<(), DiffDirsError> {
+                    let line = api::get_current_line()?;
+                    jump_to_diff_tab(dashboard_path_from_line(&line)?)
+                })
+                .noremap(true)
+                .silent(true)
+                .build(),
+        )?)
+    })?;
+
+    render_dashboard(&mut buf)
 }
 
 fn setup_keymap() -> Result<(), DiffDirsError> {
@@ -177,32 +297,77 @@ fn show_diff(args: CommandArgs) -> Result<(), DiffDirsError> {
     })
 }
 
-fn collect_file_paths(dir: &Path) -> BTreeSet<PathBuf> {
-    WalkDir::new(dir)
-        .into_iter()
-        .filter_map(|entry| {
-            match entry.map_err(|err| err.to_string()).and_then(|e| {
-                if e.file_type().is_file() {
-                    e.path()
-                        .strip_prefix(dir)
-                        .map_err(|err| err.to_string())
-                        .map(|path| Some(path.to_owned()))
-                } else {
-                    Ok(None)
-                }
-            }) {
-                Ok(path) => path,
-                Err(err) => {
-                    print!(
-                        "error occurred during walking dir: {}. err: {}",
-                        dir.to_string_lossy(),
-                        err
-                    );
-                    None
-                }
+fn build_globset(patterns: &[String]) -> Result<GlobSet, DiffDirsError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(DiffDirsError::other)?);
+    }
+    builder.build().map_err(DiffDirsError::other)
+}
+
+/// Relativizes a walked file entry to `dir`, or `None` to skip non-file entries.
+fn walk_entry_to_path_result(
+    dir: &Path,
+    path: &Path,
+    is_file: bool,
+) -> Option<Result<PathBuf, String>> {
+    is_file.then(|| {
+        path.strip_prefix(dir)
+            .map(Path::to_owned)
+            .map_err(|err| err.to_string())
+    })
+}
+
+/// Drains walk results into the set of wanted paths, logging rather than aborting on errors.
+fn collect_wanted_paths(
+    dir: &Path,
+    entries: impl Iterator<Item = Result<PathBuf, String>>,
+    is_wanted: impl Fn(&Path) -> bool,
+) -> BTreeSet<PathBuf> {
+    let mut paths = BTreeSet::new();
+    for entry in entries {
+        match entry {
+            Ok(path) if is_wanted(&path) => {
+                paths.insert(path);
             }
-        })
-        .collect()
+            Ok(_) => {}
+            Err(err) => print!(
+                "error occurred during walking dir: {}. err: {}",
+                dir.to_string_lossy(),
+                err
+            ),
+        }
+    }
+    paths
+}
+
+fn collect_file_paths(
+    dir: &Path,
+    config: &config::Config,
+) -> Result<BTreeSet<PathBuf>, DiffDirsError> {
+    let include = build_globset(config.include())?;
+    let exclude = build_globset(config.exclude())?;
+    let is_wanted = |path: &Path| -> bool {
+        (config.include().is_empty() || include.is_match(path)) && !exclude.is_match(path)
+    };
+
+    let paths = if config.respect_gitignore() {
+        let entries = WalkBuilder::new(dir).build().filter_map(|entry| match entry {
+            Ok(e) => {
+                let is_file = e.file_type().is_some_and(|ft| ft.is_file());
+                walk_entry_to_path_result(dir, e.path(), is_file)
+            }
+            Err(err) => Some(Err(err.to_string())),
+        });
+        collect_wanted_paths(dir, entries, is_wanted)
+    } else {
+        let entries = WalkDir::new(dir).into_iter().filter_map(|entry| match entry {
+            Ok(e) => walk_entry_to_path_result(dir, e.path(), e.file_type().is_file()),
+            Err(err) => Some(Err(err.to_string())),
+        });
+        collect_wanted_paths(dir, entries, is_wanted)
+    };
+    Ok(paths)
 }
 
 fn init_diff_tab(
@@ -235,6 +400,70 @@ fn split_diff_win(
     Ok(())
 }
 
+/// Reads `file` to a string, defaulting to empty for a missing file; any other read error is
+/// logged (and also defaults to empty) rather than aborting, since this is best-effort.
+fn read_to_string_or_empty(file: &Path) -> String {
+    match std::fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            print!(
+                "error occurred while reading file to seed merged output: {}. err: {}",
+                file.to_string_lossy(),
+                err
+            );
+            String::new()
+        }
+    }
+}
+
+/// Seeds a not-yet-existing three-pane output file with a diff3-style merge of `left_file` and
+/// `right_file`; a no-op if `config.seed_merged_output()` is unset or `output_file` already exists.
+fn seed_merged_output(
+    left_file: &Path,
+    right_file: &Path,
+    output_file: &Path,
+    config: &config::Config,
+) {
+    if !config.seed_merged_output() || output_file.exists() {
+        return;
+    }
+    let left = read_to_string_or_empty(left_file);
+    let right = read_to_string_or_empty(right_file);
+    let merged = merge::merge(&left, &right);
+
+    let result = output_file
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(output_file, merged));
+    if let Err(err) = result {
+        print!(
+            "error occurred while seeding merged output file: {}. err: {}",
+            output_file.to_string_lossy(),
+            err
+        );
+    }
+}
+
+/// Creates a placeholder buffer for `file`'s quickfix entry, wired to route `BufEnter` (e.g. from
+/// `:cnext`/`:cprev`) through `jump_to_diff_tab` instead of Vim just showing the empty buffer.
+/// Left unnamed so it can never collide with the real file path `open_diff_tab` edits later.
+fn make_qflist_placeholder(file: PathBuf) -> Result<Buffer, DiffDirsError> {
+    let buf = api::create_buf(true, true)?;
+    api::create_autocmd(
+        ["BufEnter"],
+        &CreateAutocmdOpts::builder()
+            .buffer(buf.clone())
+            .desc("Jump to the diff tab for this path instead of showing a placeholder buffer")
+            .callback(move |_: AutocmdCallbackArgs| -> Result<bool, DiffDirsError> {
+                jump_to_diff_tab(file.to_string_lossy().into_owned())?;
+                Ok(false)
+            })
+            .build(),
+    )?;
+    Ok(buf)
+}
+
 trait ShowDiff {
     fn base_paths(&self) -> (&Path, &Path);
     fn open_diff_tab(
@@ -244,34 +473,61 @@ trait ShowDiff {
         config: &config::Config,
     ) -> Result<(Buffer, PathBuf), DiffDirsError>;
 
-    fn diff_files(&self, config: &config::Config) -> Result<(), DiffDirsError> {
-        let files = self.make_file_set();
+    /// Path set as the quickfix entry's `filename` for `file`. `diff_files` also sets the entry's
+    /// `bufnr` to an unnamed placeholder buffer, and Vim then lists the entry under that buffer
+    /// instead of this path.
+    fn display_path(&self, file: &Path) -> PathBuf {
+        let (_, right_dir) = self.base_paths();
+        right_dir.join(file)
+    }
 
-        let first_tabpage = api::get_current_tabpage();
-        let mut is_first_cmd = true;
-        api::call_function::<_, usize>("setqflist", (Array::new(), 'r'))?;
+    /// Populates the quickfix list and `DIFF_FILES`; tabs are opened lazily, not here.
+    fn diff_files(&self, config: &config::Config) -> Result<(), DiffDirsError> {
+        let files = self.make_file_set(config)?;
+        let (left_dir, right_dir) = self.base_paths();
 
         let mut path_tab_map = BTreeMap::new();
+        let mut qflist_entries = Vec::new();
         for file in files {
-            let (modifiable_buf, modifiable_file) =
-                self.open_diff_tab(&file, if is_first_cmd { "edit" } else { "tabedit" }, config)?;
+            let status = if config.only_changed() {
+                match status::classify(&left_dir.join(&file), &right_dir.join(&file)) {
+                    Ok(Some(status)) => Some(status),
+                    Ok(None) => continue,
+                    Err(err) => {
+                        print!(
+                            "error occurred while diffing file: {}. err: {}",
+                            file.to_string_lossy(),
+                            err
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let placeholder = make_qflist_placeholder(file.clone())?;
             let mut qflist_entry = Dictionary::new();
-            qflist_entry.insert("bufnr", modifiable_buf.handle());
-            qflist_entry.insert("filename", modifiable_file.to_string_lossy());
-            qflist_entry.insert("text", file.to_string_lossy());
-            api::call_function::<_, usize>("setqflist", (Array::from_iter([qflist_entry]), 'a'))?;
-            is_first_cmd = false;
-            path_tab_map.insert(file, api::get_current_tabpage());
+            qflist_entry.insert("bufnr", placeholder.handle());
+            qflist_entry.insert("filename", self.display_path(&file).to_string_lossy());
+            let text = match status {
+                Some(status) => format!("{} {}", status.label(), file.to_string_lossy()),
+                None => file.to_string_lossy().into_owned(),
+            };
+            qflist_entry.insert("text", text);
+            qflist_entries.push(qflist_entry);
+
+            path_tab_map.insert(file, DiffEntry { tab: None, status });
         }
-        api::set_current_tabpage(&first_tabpage)?;
+        api::call_function::<_, usize>("setqflist", (Array::from_iter(qflist_entries), 'r'))?;
         DIFF_FILES.replace(path_tab_map);
         Ok(())
     }
-    fn make_file_set(&self) -> BTreeSet<PathBuf> {
+    fn make_file_set(&self, config: &config::Config) -> Result<BTreeSet<PathBuf>, DiffDirsError> {
         let (left_dir, right_dir) = self.base_paths();
-        let mut file_set: BTreeSet<PathBuf> = collect_file_paths(left_dir);
-        file_set.extend(collect_file_paths(right_dir));
-        file_set
+        let mut file_set = collect_file_paths(left_dir, config)?;
+        file_set.extend(collect_file_paths(right_dir, config)?);
+        Ok(file_set)
     }
 }
 
@@ -314,6 +570,10 @@ impl<'a> ShowDiff for ThreePaneDiff<'a> {
         (self.left_dir, self.right_dir)
     }
 
+    fn display_path(&self, file: &Path) -> PathBuf {
+        self.output_dir.join(file)
+    }
+
     fn open_diff_tab(
         &self,
         file: &Path,
@@ -329,6 +589,12 @@ impl<'a> ShowDiff for ThreePaneDiff<'a> {
         config.set_left_diff_opt(api::get_current_win())?;
 
         let modifiable_file = self.output_dir.join(file);
+        seed_merged_output(
+            &self.left_dir.join(file),
+            &self.right_dir.join(file),
+            &modifiable_file,
+            config,
+        );
         let mut cmd_mod = CommandModifiers::default();
         cmd_mod.split = Some(SplitModifier::BotRight);
         split_diff_win(&cmd_mod, &cmd_opt, &modifiable_file)?;
@@ -0,0 +1,82 @@
+use similar::{ChangeTag, TextDiff};
+
+/// Emits a pending diverging region: if only one side added lines here, keep them outright;
+/// if both sides diverge, wrap both variants in diff3-style conflict markers.
+fn flush_region<'a>(out: &mut String, left: &mut Vec<&'a str>, right: &mut Vec<&'a str>) {
+    match (left.is_empty(), right.is_empty()) {
+        (true, true) => {}
+        (false, true) => out.extend(left.drain(..)),
+        (true, false) => out.extend(right.drain(..)),
+        (false, false) => {
+            out.push_str("<<<<<<< left\n");
+            out.extend(left.drain(..));
+            out.push_str("=======\n");
+            out.extend(right.drain(..));
+            out.push_str(">>>>>>> right\n");
+        }
+    }
+    left.clear();
+    right.clear();
+}
+
+/// Builds a diff3-style merge of `left` and `right` with no common base (as if the base were
+/// empty): matching lines are emitted verbatim, a region only one side changed is resolved
+/// automatically, and a region both sides changed is wrapped in conflict markers.
+pub fn merge(left: &str, right: &str) -> String {
+    let diff = TextDiff::from_lines(left, right);
+    let mut out = String::new();
+    let mut pending_left = Vec::new();
+    let mut pending_right = Vec::new();
+
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Equal => {
+                flush_region(&mut out, &mut pending_left, &mut pending_right);
+                out.push_str(change.value());
+            }
+            ChangeTag::Delete => pending_left.push(change.value()),
+            ChangeTag::Insert => pending_right.push(change.value()),
+        }
+    }
+    flush_region(&mut out, &mut pending_left, &mut pending_right);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge;
+
+    #[test]
+    fn identical_files_pass_through_unchanged() {
+        let text = "a\nb\nc\n";
+        assert_eq!(merge(text, text), text);
+    }
+
+    #[test]
+    fn right_only_deletion_keeps_left_line() {
+        assert_eq!(merge("a\nb\nc\n", "a\nc\n"), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn left_only_deletion_keeps_right_line() {
+        assert_eq!(merge("a\nc\n", "a\nb\nc\n"), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn right_only_insertion_at_end_keeps_new_line() {
+        assert_eq!(merge("a\nb\n", "a\nb\nc\n"), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn left_only_insertion_at_start_keeps_new_line() {
+        assert_eq!(merge("a\nb\nc\n", "b\nc\n"), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn both_sides_diverging_gets_conflict_markers() {
+        assert_eq!(
+            merge("a\nleft\nc\n", "a\nright\nc\n"),
+            "a\n<<<<<<< left\nleft\n=======\nright\n>>>>>>> right\nc\n"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use similar::{ChangeTag, TextDiff};
+
+/// Above this combined byte size, diffing line-by-line risks a pathological blowup, so the pair
+/// is reported as changed without actually running the diff.
+const MAX_DIFF_BYTES: usize = 1_000_000;
+
+/// Classification of how a file differs between the left and right trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    Modified(usize),
+}
+
+impl FileStatus {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Added => "[A]".to_owned(),
+            Self::Deleted => "[D]".to_owned(),
+            Self::Modified(changes) => format!("[M:{changes}]"),
+        }
+    }
+}
+
+/// Compares the two files and returns their [`FileStatus`], or `None` if they're identical (or
+/// both missing, which shouldn't happen since `file` always comes from the union of both trees).
+pub fn classify(left: &Path, right: &Path) -> std::io::Result<Option<FileStatus>> {
+    let (left_exists, right_exists) = (left.is_file(), right.is_file());
+    match (left_exists, right_exists) {
+        (false, false) => Ok(None),
+        (false, true) => Ok(Some(FileStatus::Added)),
+        (true, false) => Ok(Some(FileStatus::Deleted)),
+        (true, true) => {
+            let left_bytes = std::fs::read(left)?;
+            let right_bytes = std::fs::read(right)?;
+            if left_bytes == right_bytes {
+                return Ok(None);
+            }
+            let changes = match (
+                std::str::from_utf8(&left_bytes),
+                std::str::from_utf8(&right_bytes),
+            ) {
+                (Ok(left_text), Ok(right_text))
+                    if left_bytes.len() + right_bytes.len() <= MAX_DIFF_BYTES =>
+                {
+                    count_changed_lines(left_text, right_text)
+                }
+                // binary, or too large to diff cheaply: bytes already known to differ
+                _ => 1,
+            };
+            Ok(Some(FileStatus::Modified(changes)))
+        }
+    }
+}
+
+/// Counts non-equal lines in the Myers shortest-edit-script between `left` and `right`.
+fn count_changed_lines(left: &str, right: &str) -> usize {
+    TextDiff::from_lines(left, right)
+        .iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, FileStatus, MAX_DIFF_BYTES};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A temp file path that's removed (if it was ever created) when the test drops it.
+    struct TempFile(PathBuf);
+
+    impl std::ops::Deref for TempFile {
+        type Target = Path;
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// Writes `contents` to a fresh temp file and returns its path; `None` skips creation
+    /// entirely, for tests that need a path that doesn't exist.
+    fn temp_file(name: &str, contents: Option<&[u8]>) -> TempFile {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("diffdirs_status_test_{name}_{id}"));
+        if let Some(contents) = contents {
+            fs::write(&path, contents).unwrap();
+        }
+        TempFile(path)
+    }
+
+    #[test]
+    fn identical_files_are_not_classified() {
+        let left = temp_file("identical_left", Some(b"a\nb\n"));
+        let right = temp_file("identical_right", Some(b"a\nb\n"));
+        assert_eq!(classify(&left, &right).unwrap(), None);
+    }
+
+    #[test]
+    fn right_only_file_is_added() {
+        let left = temp_file("added_left", None);
+        let right = temp_file("added_right", Some(b"a\n"));
+        assert_eq!(classify(&left, &right).unwrap(), Some(FileStatus::Added));
+    }
+
+    #[test]
+    fn left_only_file_is_deleted() {
+        let left = temp_file("deleted_left", Some(b"a\n"));
+        let right = temp_file("deleted_right", None);
+        assert_eq!(classify(&left, &right).unwrap(), Some(FileStatus::Deleted));
+    }
+
+    #[test]
+    fn changed_lines_are_counted() {
+        let left = temp_file("modified_left", Some(b"a\nb\nc\n"));
+        let right = temp_file("modified_right", Some(b"a\nx\nc\n"));
+        assert_eq!(
+            classify(&left, &right).unwrap(),
+            Some(FileStatus::Modified(2))
+        );
+    }
+
+    #[test]
+    fn binary_difference_falls_back_to_one_change() {
+        let left = temp_file("binary_left", Some(&[0, 159, 146, 150]));
+        let right = temp_file("binary_right", Some(&[0, 159, 146, 151]));
+        assert_eq!(
+            classify(&left, &right).unwrap(),
+            Some(FileStatus::Modified(1))
+        );
+    }
+
+    #[test]
+    fn oversized_pair_skips_the_diff() {
+        let contents = "a\n".repeat(MAX_DIFF_BYTES);
+        let left = temp_file("oversized_left", Some(contents.as_bytes()));
+        let right = temp_file("oversized_right", Some(format!("{contents}b\n").as_bytes()));
+        assert_eq!(
+            classify(&left, &right).unwrap(),
+            Some(FileStatus::Modified(1))
+        );
+    }
+}